@@ -0,0 +1,84 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+/// Worker threads spawned by default to handle accepted connections.
+const DEFAULT_WORKERS: usize = 4;
+
+/// Server-wide options controlling how requests are resolved, mirroring the
+/// knobs actix-files exposes on its `Files` service (index file, listing
+/// visibility, hidden files, bind address).
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    root_dir: PathBuf,
+    socket: SocketAddr,
+    index: Option<String>,
+    show_index: bool,
+    hidden_files: bool,
+    workers: usize,
+}
+
+impl ServerConfig {
+    pub fn new(root_dir: PathBuf) -> Self {
+        ServerConfig {
+            root_dir,
+            socket: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 5500),
+            index: None,
+            show_index: true,
+            hidden_files: false,
+            workers: DEFAULT_WORKERS,
+        }
+    }
+
+    pub fn bind(mut self, socket: SocketAddr) -> Self {
+        self.socket = socket;
+        self
+    }
+
+    /// Sets the filename served for a directory request when present, e.g. `index.html`.
+    pub fn index(mut self, index: impl Into<String>) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+
+    /// When `false`, a directory with no index file returns 404 instead of a listing.
+    pub fn show_index(mut self, show_index: bool) -> Self {
+        self.show_index = show_index;
+        self
+    }
+
+    /// When `true`, dot-prefixed entries are hidden from listings and forbidden to access directly.
+    pub fn hidden_files(mut self, hidden_files: bool) -> Self {
+        self.hidden_files = hidden_files;
+        self
+    }
+
+    /// Sets the number of worker threads that handle accepted connections.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    pub fn root_dir(&self) -> &Path {
+        &self.root_dir
+    }
+
+    pub fn socket(&self) -> SocketAddr {
+        self.socket
+    }
+
+    pub fn index_file(&self) -> Option<&str> {
+        self.index.as_deref()
+    }
+
+    pub fn shows_index(&self) -> bool {
+        self.show_index
+    }
+
+    pub fn hides_hidden_files(&self) -> bool {
+        self.hidden_files
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.workers
+    }
+}
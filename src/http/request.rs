@@ -1,18 +1,30 @@
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
 #[derive(Debug)]
 pub struct HttpRequest {
     method: Method,
     route: Route,
     version: Version,
     headers: HashMap<String, String>,
-    request_body: String,
+    request_body: Vec<u8>,
 }
 
 impl HttpRequest {
-    pub fn new(request: &str) -> Option<HttpRequest> {
-        let lines: Vec<&str> = request.lines().collect();
-        let first_line = lines.first()?;
+    /// Parses a request out of `buffer`, which must contain the full header
+    /// block (the bytes up to and including `\r\n\r\n`) plus exactly as much
+    /// of the body as the caller has read so far. The body is kept as raw
+    /// bytes rather than decoded as text, since it may be a binary upload.
+    pub fn new(buffer: &[u8]) -> Option<HttpRequest> {
+        let header_end = find_subslice(buffer, b"\r\n\r\n")?;
+        let header_str = std::str::from_utf8(&buffer[..header_end]).ok()?;
+        let request_body = buffer[header_end + 4..].to_vec();
+
+        let first_line = header_str.lines().next()?;
         let parts: Vec<&str> = first_line.split_whitespace().collect();
 
         if parts.len() != 3 {
@@ -22,10 +34,7 @@ impl HttpRequest {
         let method = Method::from_str(parts[0]).ok()?;
         let route = Route::new(parts[1]);
         let version = Version::from_str(parts[2]).ok()?;
-        let headers = HttpRequest::parse_headers(request)?;
-
-        let body_start = request.find("\r\n\r\n").map(|i| i + 4).unwrap_or(request.len());
-        let request_body = request[body_start..].to_string();
+        let headers = HttpRequest::parse_headers(header_str)?;
 
         Some(HttpRequest {
             method,
@@ -36,16 +45,15 @@ impl HttpRequest {
         })
     }
 
-    fn parse_headers(request: &str) -> Option<HashMap<String, String>> {
+    fn parse_headers(header_str: &str) -> Option<HashMap<String, String>> {
         let mut headers = HashMap::new();
-        let (_, header_str) = request.split_once("\r\n")?;
+        let (_, header_str) = header_str.split_once("\r\n")?;
 
         for line in header_str.split_terminator("\r\n") {
-            if line.is_empty() {
-                break;
-            }
             let (header, value) = line.split_once(":")?;
-            headers.insert(header.trim().to_string(), value.trim().to_string());
+            // HTTP field names are case-insensitive, so normalize to
+            // lowercase for storage and lookup (matches response.rs).
+            headers.insert(header.trim().to_lowercase(), value.trim().to_string());
         }
 
         Some(headers)
@@ -67,9 +75,114 @@ impl HttpRequest {
         &self.headers
     }
 
-    pub fn body(&self) -> &str {
+    /// The raw request body, e.g. an uploaded file's bytes.
+    pub fn body(&self) -> &[u8] {
         &self.request_body
     }
+
+    /// The `Content-Length` the client declared, if any.
+    pub fn content_length(&self) -> Option<usize> {
+        self.headers.get("content-length")?.trim().parse().ok()
+    }
+
+    pub fn range(&self) -> Option<ContentRange> {
+        self.headers.get("range").and_then(|value| ContentRange::parse(value))
+    }
+
+    /// The strongest compression the client advertised via `Accept-Encoding`,
+    /// preferring gzip over deflate when both are offered.
+    pub fn accept_encoding(&self) -> Option<ContentEncoding> {
+        let value = self.headers.get("accept-encoding")?;
+        let encodings: Vec<&str> = value.split(',').map(|e| e.trim()).collect();
+
+        if encodings.iter().any(|e| e.eq_ignore_ascii_case("gzip")) {
+            Some(ContentEncoding::Gzip)
+        } else if encodings.iter().any(|e| e.eq_ignore_ascii_case("deflate")) {
+            Some(ContentEncoding::Deflate)
+        } else {
+            None
+        }
+    }
+}
+
+/// A compression scheme negotiated via `Accept-Encoding` / `Content-Encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Deflate,
+}
+
+impl ContentEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Deflate => "deflate",
+        }
+    }
+}
+
+/// A parsed `Range` request header, per RFC 7233 (single-range only).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentRange {
+    /// `bytes=500-` — from `start` to the end of the resource.
+    From(usize),
+    /// `bytes=500-999` — an explicit inclusive `start..=end`.
+    Full(usize, usize),
+    /// `bytes=-500` — the last `len` bytes of the resource.
+    Suffix(usize),
+}
+
+impl ContentRange {
+    pub fn parse(value: &str) -> Option<ContentRange> {
+        let spec = value.trim().strip_prefix("bytes=")?;
+        let (start, end) = spec.split_once('-')?;
+
+        if start.is_empty() {
+            let len = end.parse().ok()?;
+            Some(ContentRange::Suffix(len))
+        } else if end.is_empty() {
+            let from = start.parse().ok()?;
+            Some(ContentRange::From(from))
+        } else {
+            let from = start.parse().ok()?;
+            let to = end.parse().ok()?;
+            Some(ContentRange::Full(from, to))
+        }
+    }
+
+    /// Resolves this range against a resource of `total_len` bytes, returning
+    /// an inclusive `(start, end)` byte range, or `None` if it is not
+    /// satisfiable (e.g. `start` lies past the end of the resource).
+    pub fn resolve(&self, total_len: usize) -> Option<(usize, usize)> {
+        if total_len == 0 {
+            return None;
+        }
+
+        match *self {
+            ContentRange::From(start) => {
+                if start >= total_len {
+                    None
+                } else {
+                    Some((start, total_len - 1))
+                }
+            }
+            ContentRange::Full(start, end) => {
+                if start > end || start >= total_len {
+                    None
+                } else {
+                    Some((start, end.min(total_len - 1)))
+                }
+            }
+            ContentRange::Suffix(len) => {
+                if len == 0 {
+                    None
+                } else {
+                    let len = len.min(total_len);
+                    Some((total_len - len, total_len - 1))
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -1,22 +1,48 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::net::TcpStream;
 use super::request::Version;
 
+/// Bytes read in each pass while streaming a `ResponseBody::File`.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
 #[derive(Debug)]
 pub struct HttpResponse {
     pub version: Version,
     pub status: ResponseStatus,
     pub headers: HashMap<String, String>,
-    pub response_body: Vec<u8>,
+    pub response_body: ResponseBody,
     pub current_path: String,
 }
 
+/// The response body, either buffered in memory or backed by an open file
+/// that is streamed straight to the client instead of being read in full.
+#[derive(Debug)]
+pub enum ResponseBody {
+    Bytes(Vec<u8>),
+    File { file: File, offset: u64, length: u64 },
+}
+
+impl ResponseBody {
+    fn len(&self) -> u64 {
+        match self {
+            ResponseBody::Bytes(bytes) => bytes.len() as u64,
+            ResponseBody::File { length, .. } => *length,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ResponseStatus {
     OK,
+    PartialContent,
+    NotModified,
     NotFound,
     BadRequest,
     Forbidden,
+    RangeNotSatisfiable,
     InternalServerError,
 }
 
@@ -24,9 +50,12 @@ impl ResponseStatus {
     pub fn code(&self) -> u16 {
         match self {
             ResponseStatus::OK => 200,
+            ResponseStatus::PartialContent => 206,
+            ResponseStatus::NotModified => 304,
             ResponseStatus::NotFound => 404,
             ResponseStatus::BadRequest => 400,
             ResponseStatus::Forbidden => 403,
+            ResponseStatus::RangeNotSatisfiable => 416,
             ResponseStatus::InternalServerError => 500,
         }
     }
@@ -34,9 +63,12 @@ impl ResponseStatus {
     pub fn reason(&self) -> &str {
         match self {
             ResponseStatus::OK => "OK",
+            ResponseStatus::PartialContent => "Partial Content",
+            ResponseStatus::NotModified => "Not Modified",
             ResponseStatus::NotFound => "Not Found",
             ResponseStatus::BadRequest => "Bad Request",
             ResponseStatus::Forbidden => "Forbidden",
+            ResponseStatus::RangeNotSatisfiable => "Range Not Satisfiable",
             ResponseStatus::InternalServerError => "Internal Server Error",
         }
     }
@@ -55,7 +87,7 @@ impl HttpResponse {
             version,
             status,
             headers: HashMap::new(),
-            response_body: Vec::new(),
+            response_body: ResponseBody::Bytes(Vec::new()),
             current_path: clean_path,
         };
         response.add_header("Accept-Ranges", "bytes");
@@ -67,25 +99,65 @@ impl HttpResponse {
     }
 
     pub fn set_body(&mut self, body: impl Into<Vec<u8>>) {
-        self.response_body = body.into();
-        self.add_header("Content-Length", &self.response_body.len().to_string());
+        let body = body.into();
+        self.add_header("Content-Length", &body.len().to_string());
+        self.response_body = ResponseBody::Bytes(body);
     }
 
-    pub fn to_string(&self) -> Vec<u8> {
-        let mut response = format!(
+    /// Drops the body while leaving `Content-Length` and other headers
+    /// untouched, for responses (e.g. to `HEAD`) that must report the size
+    /// of the resource without sending its content.
+    pub fn strip_body(&mut self) {
+        self.response_body = ResponseBody::Bytes(Vec::new());
+    }
+
+    /// Streams `length` bytes of `file` starting at `offset`, rather than
+    /// buffering them, so large files don't have to be read into memory.
+    pub fn set_file_body(&mut self, file: File, offset: u64, length: u64) {
+        self.add_header("Content-Length", &length.to_string());
+        self.response_body = ResponseBody::File { file, offset, length };
+    }
+
+    fn header_bytes(&self) -> Vec<u8> {
+        let mut header = format!(
             "{} {}\r\n",
             self.version,
             self.status
         ).into_bytes();
 
         for (key, value) in &self.headers {
-            response.extend(format!("{}: {}\r\n", key, value).into_bytes());
+            header.extend(format!("{}: {}\r\n", key, value).into_bytes());
         }
 
-        response.extend(b"\r\n");
-        response.extend(&self.response_body);
+        header.extend(b"\r\n");
+        header
+    }
 
-        response
+    /// Writes the status line, headers and body to `stream`, reading a
+    /// `ResponseBody::File` in fixed-size chunks rather than all at once.
+    pub fn write_to(&self, stream: &mut TcpStream) -> io::Result<()> {
+        stream.write_all(&self.header_bytes())?;
+
+        match &self.response_body {
+            ResponseBody::Bytes(bytes) => stream.write_all(bytes),
+            ResponseBody::File { file, offset, length } => {
+                let mut file = file.try_clone()?;
+                file.seek(SeekFrom::Start(*offset))?;
+
+                let mut remaining = *length;
+                let mut buf = [0u8; STREAM_CHUNK_SIZE];
+                while remaining > 0 {
+                    let want = remaining.min(STREAM_CHUNK_SIZE as u64) as usize;
+                    let read = file.read(&mut buf[..want])?;
+                    if read == 0 {
+                        break;
+                    }
+                    stream.write_all(&buf[..read])?;
+                    remaining -= read as u64;
+                }
+                Ok(())
+            }
+        }
     }
 
     pub fn formatted_output(&self) -> String {
@@ -1,36 +1,81 @@
 use std::{
     io::{self, Read, Write},
-    net::{TcpListener, TcpStream, SocketAddr, Ipv4Addr},
+    net::{TcpListener, TcpStream},
     fs,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
     env,
 };
-use crate::http::request::{HttpRequest, Method, Version};
+use crate::config::ServerConfig;
+use crate::http::date;
+use crate::http::request::{ContentEncoding, HttpRequest, Method, Version};
 use crate::http::response::{HttpResponse, ResponseStatus};
+use crate::pool::ThreadPool;
 use url_escape::decode;
 use infer;
+use flate2::{write::GzEncoder, write::ZlibEncoder, Compression};
 
+mod config;
+mod pool;
 mod http {
+    pub mod date;
     pub mod request;
     pub mod response;
 }
 
-fn create_socket() -> SocketAddr {
-    SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 5500)
+/// Bytes read from the socket per `read` call while accumulating a request.
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// Reads from `stream` into a growable buffer until the `\r\n\r\n` header
+/// terminator is found, then reads exactly as many further bytes as the
+/// `Content-Length` header declares, so large header sets and bodied
+/// requests aren't truncated at an arbitrary fixed size.
+fn read_request(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+    let mut header_end = None;
+
+    while header_end.is_none() {
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(buffer);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        header_end = buffer.windows(4).position(|window| window == b"\r\n\r\n");
+    }
+
+    let body_start = header_end.unwrap() + 4;
+    let content_length = HttpRequest::new(&buffer)
+        .and_then(|request| request.content_length())
+        .unwrap_or(0);
+
+    while buffer.len() < body_start + content_length {
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    Ok(buffer)
 }
 
-fn handle_client(mut stream: TcpStream, root_dir: &Path) -> io::Result<()> {
-    let mut buffer = [0; 1024];
-    stream.read(&mut buffer)?;
+fn handle_client(mut stream: TcpStream, config: &ServerConfig) -> io::Result<()> {
+    let buffer = read_request(&mut stream)?;
 
-    let request_str = String::from_utf8_lossy(&buffer);
-    println!("Received request:\n{}", request_str);
+    println!("Received request:\n{}", String::from_utf8_lossy(&buffer));
 
-    match HttpRequest::new(&request_str) {
+    match HttpRequest::new(&buffer) {
         Some(request) => {
             println!("Parsed request: {:?}", request);
             let response = match request.method() {
-                Method::Get => handle_get_request(&request, root_dir),
+                Method::Get => handle_get_request(&request, config),
+                Method::Head => {
+                    let mut response = handle_get_request(&request, config);
+                    response.strip_body();
+                    response
+                },
                 Method::Post => handle_post_request(&request),
                 _ => {
                     println!("Unsupported method: {:?}", request.method());
@@ -40,13 +85,13 @@ fn handle_client(mut stream: TcpStream, root_dir: &Path) -> io::Result<()> {
 
             println!("Response: {}", response.formatted_output());
             println!("\n{}", response.http_response_string());
-            
-            stream.write_all(&response.to_string())?;
+
+            response.write_to(&mut stream)?;
         }
         None => {
             println!("Failed to parse request");
             let error_response = HttpResponse::new(Version::V1_1, ResponseStatus::BadRequest, String::from("Invalid Request"));
-            stream.write_all(&error_response.to_string())?;
+            error_response.write_to(&mut stream)?;
         }
     }
 
@@ -54,18 +99,40 @@ fn handle_client(mut stream: TcpStream, root_dir: &Path) -> io::Result<()> {
     Ok(())
 }
 
-fn handle_get_request(request: &HttpRequest, root_dir: &Path) -> HttpResponse {
+fn handle_get_request(request: &HttpRequest, config: &ServerConfig) -> HttpResponse {
+    let root_dir = config.root_dir();
     let decoded_path = decode(request.route().path());
     let requested_path = root_dir.join(decoded_path.trim_start_matches('/'));
 
     println!("Root dir: {:?}", root_dir);
     println!("Requested path: {:?}", requested_path);
 
+    if config.hides_hidden_files() && has_hidden_component(&requested_path, root_dir) {
+        println!("Hidden path access attempted: {:?}", requested_path);
+        return HttpResponse::new(request.version().clone(), ResponseStatus::Forbidden, "Forbidden".to_string());
+    }
+
     match is_safe_path(root_dir, &requested_path) {
         Ok(true) => {
             if requested_path.is_dir() {
-                println!("Serving directory: {:?}", requested_path);
-                handle_directory_listing(request, &requested_path)
+                let index_path = config.index_file()
+                    .map(|index| requested_path.join(index))
+                    .filter(|path| path.is_file());
+
+                match index_path {
+                    Some(index_path) => {
+                        println!("Serving index file: {:?}", index_path);
+                        handle_file_request(request, &index_path)
+                    },
+                    None if config.shows_index() => {
+                        println!("Serving directory: {:?}", requested_path);
+                        handle_directory_listing(request, &requested_path, config)
+                    },
+                    None => {
+                        println!("Listing disabled for: {:?}", requested_path);
+                        HttpResponse::new(request.version().clone(), ResponseStatus::NotFound, "Not Found".to_string())
+                    }
+                }
             } else if requested_path.is_file() {
                 println!("Serving file: {:?}", requested_path);
                 handle_file_request(request, &requested_path)
@@ -85,6 +152,14 @@ fn handle_get_request(request: &HttpRequest, root_dir: &Path) -> HttpResponse {
     }
 }
 
+/// Whether any path component of `requested_path` beyond `root_dir` starts
+/// with a dot, i.e. it or an ancestor directory is a hidden entry.
+fn has_hidden_component(requested_path: &Path, root_dir: &Path) -> bool {
+    requested_path.strip_prefix(root_dir)
+        .map(|relative| relative.iter().any(|part| part.to_string_lossy().starts_with('.')))
+        .unwrap_or(false)
+}
+
 fn is_safe_path(root_dir: &Path, requested_path: &Path) -> io::Result<bool> {
     let canonicalized_root = root_dir.canonicalize()?;
     let requested_path_buf = requested_path.to_path_buf();
@@ -105,7 +180,7 @@ fn is_safe_path(root_dir: &Path, requested_path: &Path) -> io::Result<bool> {
 }
 
 
-fn handle_directory_listing(request: &HttpRequest, dir_path: &Path) -> HttpResponse {
+fn handle_directory_listing(request: &HttpRequest, dir_path: &Path, config: &ServerConfig) -> HttpResponse {
     let mut response = HttpResponse::new(request.version().clone(), ResponseStatus::OK, dir_path.to_string_lossy().into_owned());
     let mut content = String::new();
     content.push_str(r#"<!DOCTYPE html>
@@ -178,6 +253,9 @@ fn handle_directory_listing(request: &HttpRequest, dir_path: &Path) -> HttpRespo
                 if let Ok(entry) = entry {
                     let path = entry.path();
                     let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    if config.hides_hidden_files() && name.starts_with('.') {
+                        continue;
+                    }
                     let link = format!("{}", name);
                     let icon_class = if path.is_dir() { "folder-icon" } else { "file-icon" };
                     content.push_str(&format!(r#"<li><a href="{}"><span class="{}"></span>{}</a></li>"#, link, icon_class, name));
@@ -201,38 +279,162 @@ fn handle_directory_listing(request: &HttpRequest, dir_path: &Path) -> HttpRespo
     content.push_str("</ul></body></html>");
 
     response.add_header("Content-Type", "text/html");
-    response.set_body(content.into_bytes());
+
+    match request.accept_encoding() {
+        Some(encoding) => match compress(content.as_bytes(), encoding) {
+            Ok(compressed) => {
+                response.add_header("Content-Encoding", encoding.as_str());
+                response.add_header("Vary", "Accept-Encoding");
+                response.set_body(compressed);
+            },
+            Err(e) => {
+                response.status = ResponseStatus::InternalServerError;
+                response.set_body(format!("An error occurred: {}", e).into_bytes());
+            }
+        },
+        None => response.set_body(content.into_bytes()),
+    }
     response
 }
+fn is_compressible(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || content_type == "application/javascript"
+        || content_type == "application/json"
+        || content_type == "image/svg+xml"
+}
+
+fn compress(body: &[u8], encoding: ContentEncoding) -> io::Result<Vec<u8>> {
+    match encoding {
+        ContentEncoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()
+        },
+        ContentEncoding::Deflate => {
+            // The `deflate` content-coding is the zlib format (RFC 1950), not
+            // raw DEFLATE (RFC 1951), so this needs the zlib-wrapping encoder.
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()
+        },
+    }
+}
+
+/// Whether `request`'s validators (`If-None-Match` takes priority over
+/// `If-Modified-Since`, per RFC 7232) show the client already has the
+/// current representation of the resource cached.
+fn is_not_modified(request: &HttpRequest, etag: &str, modified: SystemTime) -> bool {
+    if let Some(if_none_match) = request.headers().get("if-none-match") {
+        return if_none_match.split(',').any(|candidate| {
+            let candidate = candidate.trim();
+            candidate == "*" || candidate == etag
+        });
+    }
+
+    if let Some(if_modified_since) = request.headers().get("if-modified-since") {
+        if let Some(since) = date::parse(if_modified_since) {
+            // `Last-Modified` is only second-precision, so compare at that
+            // precision too, or a sub-second mtime never compares as equal.
+            let modified_secs = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+            let since_secs = since.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+            return modified_secs <= since_secs;
+        }
+    }
+
+    false
+}
+
+fn guess_content_type(file_path: &Path) -> String {
+    infer::get_from_path(file_path)
+        .ok()
+        .flatten()
+        .map(|t| t.mime_type().to_string())
+        .unwrap_or_else(|| {
+            // Fallback to common MIME types based on file extension
+            match file_path.extension().and_then(|e| e.to_str()) {
+                Some("html") | Some("htm") => "text/html",
+                Some("css") => "text/css",
+                Some("js") => "application/javascript",
+                Some("json") => "application/json",
+                Some("txt") => "text/plain",
+                Some("png") => "image/png",
+                Some("jpg") | Some("jpeg") => "image/jpeg",
+                Some("gif") => "image/gif",
+                Some("svg") => "image/svg+xml",
+                Some("pdf") => "application/pdf",
+                Some("mp4") => "video/mp4",
+                Some("webm") => "video/webm",
+                Some("ogg") => "video/ogg",
+                _ => "application/octet-stream",
+            }.to_string()
+        })
+}
+
 fn handle_file_request(request: &HttpRequest, file_path: &Path) -> HttpResponse {
     let mut response = HttpResponse::new(request.version().clone(), ResponseStatus::OK, file_path.to_string_lossy().into_owned());
 
-    match fs::read(file_path) {
-        Ok(contents) => {
-            let content_type = infer::get(&contents)
-                .map(|t| t.mime_type().to_string())
-                .unwrap_or_else(|| {
-                    // Fallback to common MIME types based on file extension
-                    match file_path.extension().and_then(|e| e.to_str()) {
-                        Some("html") | Some("htm") => "text/html",
-                        Some("css") => "text/css",
-                        Some("js") => "application/javascript",
-                        Some("json") => "application/json",
-                        Some("txt") => "text/plain",
-                        Some("png") => "image/png",
-                        Some("jpg") | Some("jpeg") => "image/jpeg",
-                        Some("gif") => "image/gif",
-                        Some("svg") => "image/svg+xml",
-                        Some("pdf") => "application/pdf",
-                        Some("mp4") => "video/mp4",
-                        Some("webm") => "video/webm",
-                        Some("ogg") => "video/ogg",
-                        _ => "application/octet-stream",
-                    }.to_string()
-                });
+    match fs::File::open(file_path) {
+        Ok(file) => {
+            let metadata = match file.metadata() {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    response.status = ResponseStatus::InternalServerError;
+                    response.set_body(format!("An error occurred: {}", e).into_bytes());
+                    return response;
+                }
+            };
+            let total_len = metadata.len();
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let mtime_secs = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+            let etag = format!("W/\"{}-{}\"", mtime_secs, total_len);
 
+            response.add_header("Last-Modified", &date::format(modified));
+            response.add_header("ETag", &etag);
+
+            if is_not_modified(request, &etag, modified) {
+                response.status = ResponseStatus::NotModified;
+                response.set_body(Vec::new());
+                return response;
+            }
+
+            let content_type = guess_content_type(file_path);
             response.add_header("Content-Type", &content_type);
-            response.set_body(contents);
+
+            match request.range().and_then(|range| range.resolve(total_len as usize)) {
+                Some((start, end)) => {
+                    // Byte ranges over a compressed stream are ambiguous, so ranged
+                    // responses are always served raw, straight from the file.
+                    response.status = ResponseStatus::PartialContent;
+                    response.add_header("Content-Range", &format!("bytes {}-{}/{}", start, end, total_len));
+                    response.set_file_body(file, start as u64, (end - start + 1) as u64);
+                },
+                None if request.range().is_some() => {
+                    response.status = ResponseStatus::RangeNotSatisfiable;
+                    response.add_header("Content-Range", &format!("bytes */{}", total_len));
+                    response.set_body(Vec::new());
+                },
+                None => {
+                    let encoding = request.accept_encoding()
+                        .filter(|_| is_compressible(&content_type));
+
+                    match encoding {
+                        Some(encoding) => match fs::read(file_path).and_then(|bytes| compress(&bytes, encoding)) {
+                            Ok(compressed) => {
+                                response.add_header("Content-Encoding", encoding.as_str());
+                                response.add_header("Vary", "Accept-Encoding");
+                                response.set_body(compressed);
+                            },
+                            Err(e) => {
+                                response.status = ResponseStatus::InternalServerError;
+                                response.set_body(format!("An error occurred: {}", e).into_bytes());
+                            }
+                        },
+                        None => {
+                            response.set_file_body(file, 0, total_len);
+                        }
+                    }
+                }
+            }
         },
         Err(e) => {
             match e.kind() {
@@ -259,35 +461,95 @@ fn handle_post_request(request: &HttpRequest) -> HttpResponse {
     let mut response = HttpResponse::new(request.version().clone(), ResponseStatus::OK, request.route().path().to_string());
     let body = format!(
         "<html><body><h1>Received POST request</h1><p>Body: {}</p></body></html>",
-        request.body()
+        String::from_utf8_lossy(request.body())
     );
     response.add_header("Content-Type", "text/html");
     response.set_body(body);
     response
 }
 
-fn serve(socket: SocketAddr, root_dir: PathBuf) -> io::Result<()> {
-    let listener = TcpListener::bind(socket)?;
-    println!("Server listening on {} serving directory {:?}", socket, root_dir);
-    
+fn serve(config: ServerConfig) -> io::Result<()> {
+    let listener = TcpListener::bind(config.socket())?;
+    println!(
+        "Server listening on {} serving directory {:?} with {} worker(s)",
+        config.socket(), config.root_dir(), config.worker_count()
+    );
+
+    let config = Arc::new(config);
+    let pool = ThreadPool::new(config.worker_count());
+
     for stream in listener.incoming() {
         let stream = stream?;
-        let root_dir = root_dir.clone();
-        if let Err(e) = handle_client(stream, &root_dir) {
-            eprintln!("Error handling client: {}", e);
-        }
+        let config = Arc::clone(&config);
+        pool.execute(move || {
+            if let Err(e) = handle_client(stream, &config) {
+                eprintln!("Error handling client: {}", e);
+            }
+        });
     }
     Ok(())
 }
 
-fn main() -> io::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    let root_dir = if args.len() > 1 {
-        PathBuf::from(&args[1])
-    } else {
-        env::current_dir()?
-    };
+/// Parses `--bind ADDR`, `--index FILE`, `--no-listing`, `--hide-dotfiles`
+/// and `--workers N` on top of the existing positional root-directory argument.
+fn parse_args(args: &[String]) -> io::Result<ServerConfig> {
+    let mut root_dir = env::current_dir()?;
+    let mut socket = None;
+    let mut index = None;
+    let mut show_index = true;
+    let mut hidden_files = false;
+    let mut workers = None;
 
-    let socket = create_socket();
-    serve(socket, root_dir)
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--bind" => {
+                if let Some(value) = args.get(i + 1) {
+                    socket = value.parse().ok();
+                    i += 1;
+                }
+            },
+            "--index" => {
+                if let Some(value) = args.get(i + 1) {
+                    index = Some(value.clone());
+                    i += 1;
+                }
+            },
+            "--no-listing" => show_index = false,
+            "--hide-dotfiles" => hidden_files = true,
+            "--workers" => {
+                if let Some(value) = args.get(i + 1) {
+                    workers = value.parse().ok();
+                    i += 1;
+                }
+            },
+            flag if flag.starts_with("--") => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unrecognized flag: {}", flag),
+                ));
+            },
+            path => root_dir = PathBuf::from(path),
+        }
+        i += 1;
+    }
+
+    let mut config = ServerConfig::new(root_dir).show_index(show_index).hidden_files(hidden_files);
+    if let Some(socket) = socket {
+        config = config.bind(socket);
+    }
+    if let Some(index) = index {
+        config = config.index(index);
+    }
+    if let Some(workers) = workers {
+        config = config.workers(workers);
+    }
+
+    Ok(config)
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = parse_args(&args)?;
+    serve(config)
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// A fixed-size pool of worker threads pulling connections off a bounded,
+/// shared queue, so one slow client can't block the others.
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: Option<SyncSender<Job>>,
+}
+
+impl ThreadPool {
+    /// Spawns `size` worker threads. Panics if `size` is 0.
+    pub fn new(size: usize) -> ThreadPool {
+        assert!(size > 0, "thread pool needs at least one worker");
+
+        let (sender, receiver) = sync_channel(size);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..size)
+            .map(|_| Worker::new(Arc::clone(&receiver)))
+            .collect();
+
+        ThreadPool { workers, sender: Some(sender) }
+    }
+
+    /// Queues `job` for the next free worker, blocking if the queue is full.
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(Box::new(job));
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // Dropping the sender unblocks each worker's `recv` with an `Err`,
+        // so they exit their loop and can be joined below.
+        drop(self.sender.take());
+
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+struct Worker {
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(receiver: Arc<Mutex<std::sync::mpsc::Receiver<Job>>>) -> Worker {
+        let thread = thread::spawn(move || loop {
+            // Drop the lock before running the job, so other workers can
+            // pull their next job while this one is busy.
+            let job = receiver.lock().unwrap().recv();
+            match job {
+                Ok(job) => job(),
+                Err(_) => break,
+            }
+        });
+
+        Worker { thread: Some(thread) }
+    }
+}